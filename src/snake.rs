@@ -1,11 +1,14 @@
 use piston_window::types::Color;
 use piston_window::{Context, G2d};
-use std::collections::LinkedList;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, LinkedList, VecDeque};
 
 use crate::draw::draw_block;
 
 const SNAKE_COLOR: Color = [0.00, 0.80, 0.00, 1.0];
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Direction {
     Up,
     Down,
@@ -30,10 +33,22 @@ struct Block {
     y: i32,
 }
 
+/// Visited BFS cell -> (previous cell, move that reached it).
+type CameFromMap = HashMap<(i32, i32), ((i32, i32), Direction)>;
+
+/// `Bounded` lets the head run off the grid; `Wrapped` reappears on the opposite edge.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum BoardMode {
+    Bounded,
+    Wrapped,
+}
+
 pub struct Snake {
     direction: Direction,
     body: LinkedList<Block>,
     tail: Option<Block>,
+    board_mode: BoardMode,
+    board_size: (i32, i32),
 }
 
 impl Snake {
@@ -47,6 +62,22 @@ impl Snake {
             direction: Direction::Right,
             body,
             tail: None,
+            board_mode: BoardMode::Bounded,
+            board_size: (0, 0),
+        }
+    }
+
+    /// Sets the wrap-around dimensions used when `mode` is `Wrapped`.
+    pub fn set_board_mode(&mut self, width: i32, height: i32, mode: BoardMode) {
+        self.board_size = (width, height);
+        self.board_mode = mode;
+    }
+
+    fn wrap_coord(&self, value: i32, size: i32) -> i32 {
+        match self.board_mode {
+            BoardMode::Bounded => value,
+            BoardMode::Wrapped if size > 0 => (value + size) % size,
+            BoardMode::Wrapped => value,
         }
     }
 
@@ -61,6 +92,11 @@ impl Snake {
         (head_block.x, head_block.y)
     }
 
+    pub fn tail_position(&self) -> (i32, i32) {
+        let tail_block = self.body.back().unwrap();
+        (tail_block.x, tail_block.y)
+    }
+
     pub fn move_forward(&mut self, dir: Option<Direction>) {
         if let Some(d) = dir {
             self.direction = d
@@ -71,18 +107,18 @@ impl Snake {
         let new_block = match self.direction {
             Direction::Up => Block {
                 x: last_x,
-                y: last_y - 1,
+                y: self.wrap_coord(last_y - 1, self.board_size.1),
             },
             Direction::Down => Block {
                 x: last_x,
-                y: last_y + 1,
+                y: self.wrap_coord(last_y + 1, self.board_size.1),
             },
             Direction::Left => Block {
-                x: last_x - 1,
+                x: self.wrap_coord(last_x - 1, self.board_size.0),
                 y: last_y,
             },
             Direction::Right => Block {
-                x: last_x + 1,
+                x: self.wrap_coord(last_x + 1, self.board_size.0),
                 y: last_y,
             },
         };
@@ -104,10 +140,10 @@ impl Snake {
         };
 
         match moving_dir {
-            Direction::Up => (head_x, head_y - 1),
-            Direction::Down => (head_x, head_y + 1),
-            Direction::Left => (head_x - 1, head_y),
-            Direction::Right => (head_x + 1, head_y),
+            Direction::Up => (head_x, self.wrap_coord(head_y - 1, self.board_size.1)),
+            Direction::Down => (head_x, self.wrap_coord(head_y + 1, self.board_size.1)),
+            Direction::Left => (self.wrap_coord(head_x - 1, self.board_size.0), head_y),
+            Direction::Right => (self.wrap_coord(head_x + 1, self.board_size.0), head_y),
         }
     }
 
@@ -116,6 +152,54 @@ impl Snake {
         self.body.push_back(blk);
     }
 
+    /// Re-appends the block `move_forward` last popped off the tail.
+    pub fn grow(&mut self) {
+        if let Some(blk) = self.tail.take() {
+            self.body.push_back(blk);
+        }
+    }
+
+    pub fn occupied_cells(&self) -> HashSet<(i32, i32)> {
+        self.body.iter().map(|block| (block.x, block.y)).collect()
+    }
+
+    /// For sending the snake's state over the wire and rebuilding it with `from_snapshot`.
+    pub fn snapshot(&self) -> SnakeSnapshot {
+        let body = self
+            .body
+            .iter()
+            .map(|block| BlockSnapshot {
+                x: block.x,
+                y: block.y,
+            })
+            .collect();
+        let (x, y) = self.head_position();
+
+        SnakeSnapshot {
+            body,
+            direction: self.direction,
+            head: BlockSnapshot { x, y },
+        }
+    }
+
+    pub fn from_snapshot(snapshot: &SnakeSnapshot) -> Snake {
+        let mut body: LinkedList<Block> = LinkedList::new();
+        for block in &snapshot.body {
+            body.push_back(Block {
+                x: block.x,
+                y: block.y,
+            });
+        }
+
+        Snake {
+            direction: snapshot.direction,
+            body,
+            tail: None,
+            board_mode: BoardMode::Bounded,
+            board_size: (0, 0),
+        }
+    }
+
     pub fn overlap_tail(&self, x: i32, y: i32) -> bool {
         let mut ch = 0;
         for block in &self.body {
@@ -130,6 +214,414 @@ impl Snake {
         }
         false
     }
+
+    /// BFS shortest path to `food`, falling back to whichever safe neighbor
+    /// leaves the most open space if no path exists or it would trap the snake.
+    pub fn autopilot(&self, board_w: i32, board_h: i32, food: (i32, i32)) -> Option<Direction> {
+        let blocked = self.blocked_cells();
+        let head = self.head_position();
+        let len = self.body.len();
+        let opposite = self.direction.opposite();
+
+        let safe_move = |dir: Direction| -> Option<(Direction, usize)> {
+            if dir == opposite {
+                return None;
+            }
+            let next = self
+                .neighbors(head, board_w, board_h)
+                .iter()
+                .find(|&&(d, _)| d == dir)
+                .map(|&(_, pos)| pos)
+                .unwrap();
+            if !Self::in_bounds(next, board_w, board_h) || blocked.contains(&next) {
+                return None;
+            }
+            let reachable = self.flood_fill(next, board_w, board_h, &blocked);
+            Some((dir, reachable))
+        };
+
+        if let Some(path_dir) = self.bfs_first_step(head, food, board_w, board_h, &blocked) {
+            if let Some((_, reachable)) = safe_move(path_dir) {
+                if reachable >= len {
+                    return Some(path_dir);
+                }
+            }
+        }
+
+        [
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ]
+        .iter()
+        .filter_map(|&dir| safe_move(dir))
+        .max_by_key(|&(_, reachable)| reachable)
+        .map(|(dir, _)| dir)
+    }
+
+    /// Body cells except the tail, which will have vacated by the next move.
+    fn blocked_cells(&self) -> HashSet<(i32, i32)> {
+        let mut iter = self.body.iter().peekable();
+        let mut blocked = HashSet::new();
+        while let Some(block) = iter.next() {
+            if iter.peek().is_some() {
+                blocked.insert((block.x, block.y));
+            }
+        }
+        blocked
+    }
+
+    fn in_bounds(pos: (i32, i32), board_w: i32, board_h: i32) -> bool {
+        pos.0 >= 0 && pos.0 < board_w && pos.1 >= 0 && pos.1 < board_h
+    }
+
+    /// Wraps against `board_w`/`board_h`, not `self.board_size`, so a search
+    /// stays consistent with whatever dimensions it was given.
+    fn neighbors(&self, pos: (i32, i32), board_w: i32, board_h: i32) -> [(Direction, (i32, i32)); 4] {
+        [
+            (Direction::Up, (pos.0, self.wrap_coord(pos.1 - 1, board_h))),
+            (Direction::Down, (pos.0, self.wrap_coord(pos.1 + 1, board_h))),
+            (Direction::Left, (self.wrap_coord(pos.0 - 1, board_w), pos.1)),
+            (Direction::Right, (self.wrap_coord(pos.0 + 1, board_w), pos.1)),
+        ]
+    }
+
+    fn bfs_first_step(
+        &self,
+        start: (i32, i32),
+        goal: (i32, i32),
+        board_w: i32,
+        board_h: i32,
+        blocked: &HashSet<(i32, i32)>,
+    ) -> Option<Direction> {
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::new();
+        let mut came_from: CameFromMap = HashMap::new();
+
+        visited.insert(start);
+        queue.push_back(start);
+
+        while let Some(current) = queue.pop_front() {
+            if current == goal {
+                return Self::reconstruct_first_step(start, current, &came_from);
+            }
+
+            for &(dir, next) in &self.neighbors(current, board_w, board_h) {
+                if !Self::in_bounds(next, board_w, board_h) {
+                    continue;
+                }
+                if blocked.contains(&next) || visited.contains(&next) {
+                    continue;
+                }
+                visited.insert(next);
+                came_from.insert(next, (current, dir));
+                queue.push_back(next);
+            }
+        }
+
+        None
+    }
+
+    fn reconstruct_first_step(
+        start: (i32, i32),
+        mut current: (i32, i32),
+        came_from: &CameFromMap,
+    ) -> Option<Direction> {
+        let mut first_step = None;
+        while let Some(&(prev, dir)) = came_from.get(&current) {
+            if prev == start {
+                first_step = Some(dir);
+                break;
+            }
+            current = prev;
+        }
+        first_step
+    }
+
+    fn flood_fill(
+        &self,
+        start: (i32, i32),
+        board_w: i32,
+        board_h: i32,
+        blocked: &HashSet<(i32, i32)>,
+    ) -> usize {
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::new();
+
+        visited.insert(start);
+        queue.push_back(start);
+
+        while let Some(current) = queue.pop_front() {
+            for &(_, next) in &self.neighbors(current, board_w, board_h) {
+                if !Self::in_bounds(next, board_w, board_h) {
+                    continue;
+                }
+                if blocked.contains(&next) || visited.contains(&next) {
+                    continue;
+                }
+                visited.insert(next);
+                queue.push_back(next);
+            }
+        }
+
+        visited.len()
+    }
+}
+
+/// Drives a snake around a precomputed Hamiltonian cycle over a `width` x
+/// `height` board; if the area is odd, the cycle covers the larger of the two
+/// even-area rectangles left by dropping the last column or the last row.
+pub struct HamiltonianSolver {
+    cycle_cells: Vec<(i32, i32)>,
+    cell_position: HashMap<(i32, i32), usize>,
+    food: Option<(i32, i32)>,
+}
+
+impl HamiltonianSolver {
+    /// Returns `None` for `width`/`height` smaller than 2: a single row or
+    /// column is a path graph and has no Hamiltonian cycle.
+    pub fn new(width: i32, height: i32) -> Option<HamiltonianSolver> {
+        if width < 2 || height < 2 {
+            return None;
+        }
+
+        let (raw_cycle, transposed) = if width % 2 == 0 {
+            (Self::build_even_width_cycle(width, height), false)
+        } else if height % 2 == 0 {
+            (Self::build_even_width_cycle(height, width), true)
+        } else if width >= height {
+            (Self::build_even_width_cycle(width - 1, height), false)
+        } else {
+            (Self::build_even_width_cycle(height - 1, width), true)
+        };
+
+        let cycle_cells: Vec<(i32, i32)> = if transposed {
+            raw_cycle.into_iter().map(|(x, y)| (y, x)).collect()
+        } else {
+            raw_cycle
+        };
+
+        let mut cell_position = HashMap::with_capacity(cycle_cells.len());
+        for (i, &cell) in cycle_cells.iter().enumerate() {
+            cell_position.insert(cell, i);
+        }
+
+        Some(HamiltonianSolver {
+            cycle_cells,
+            cell_position,
+            food: None,
+        })
+    }
+
+    /// Builds the cycle for a board with even `width`.
+    fn build_even_width_cycle(width: i32, height: i32) -> Vec<(i32, i32)> {
+        let mut cells = Vec::with_capacity((width * height) as usize);
+
+        for y in 0..height {
+            cells.push((0, y));
+        }
+
+        let mut x = 1;
+        while x < width {
+            if x % 2 == 1 {
+                for y in (1..height).rev() {
+                    cells.push((x, y));
+                }
+            } else {
+                for y in 1..height {
+                    cells.push((x, y));
+                }
+            }
+            x += 1;
+        }
+
+        for x in (1..width).rev() {
+            cells.push((x, 0));
+        }
+
+        cells
+    }
+
+    pub fn set_food(&mut self, food: (i32, i32)) {
+        self.food = Some(food);
+    }
+
+    pub fn next_cycle_move(&self, snake: &Snake) -> Direction {
+        let head = snake.head_position();
+        // Off-cycle cell (fallback sub-region board): keep the current heading.
+        let head_pos = match self.cell_position.get(&head) {
+            Some(&pos) => pos,
+            None => return snake.head_direction(),
+        };
+        let tail_pos = self
+            .cell_position
+            .get(&snake.tail_position())
+            .copied()
+            .unwrap_or(head_pos);
+
+        let target = self.cycle_cells[self.shortcut_target(head, head_pos, tail_pos)];
+        Self::direction_between(head, target)
+    }
+
+    fn forward_gap(&self, from: usize, to: usize) -> usize {
+        let n = self.cycle_cells.len();
+        (to + n - from) % n
+    }
+
+    /// Takes a shortcut toward the food if one exists without passing the tail.
+    fn shortcut_target(&self, head: (i32, i32), head_pos: usize, tail_pos: usize) -> usize {
+        let n = self.cycle_cells.len();
+        let tail_gap = self.forward_gap(head_pos, tail_pos);
+        let next_pos = (head_pos + 1) % n;
+
+        let food_pos = match self.food.and_then(|food| self.cell_position.get(&food)) {
+            Some(&pos) => pos,
+            None => return next_pos,
+        };
+        let food_gap = self.forward_gap(head_pos, food_pos);
+
+        let mut best = next_pos;
+        let mut best_gap = 1;
+
+        for &(dx, dy) in &[(0, -1), (0, 1), (-1, 0), (1, 0)] {
+            let neighbor = (head.0 + dx, head.1 + dy);
+            if let Some(&pos) = self.cell_position.get(&neighbor) {
+                let gap = self.forward_gap(head_pos, pos);
+                if gap > best_gap && gap <= food_gap && gap < tail_gap {
+                    best = pos;
+                    best_gap = gap;
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Panics if `to` isn't one of `from`'s four grid-neighbors.
+    fn direction_between(from: (i32, i32), to: (i32, i32)) -> Direction {
+        match (to.0 - from.0, to.1 - from.1) {
+            (0, -1) => Direction::Up,
+            (0, 1) => Direction::Down,
+            (-1, 0) => Direction::Left,
+            (1, 0) => Direction::Right,
+            delta => panic!("HamiltonianSolver cycle step {:?} is not grid-adjacent", delta),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct BlockSnapshot {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnakeSnapshot {
+    pub body: Vec<BlockSnapshot>,
+    pub direction: Direction,
+    pub head: BlockSnapshot,
+}
+
+/// Board size, snake body, and food position, for an external control process.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GameState {
+    pub board_width: i32,
+    pub board_height: i32,
+    pub snake: SnakeSnapshot,
+    pub food: BlockSnapshot,
+}
+
+impl GameState {
+    pub fn new(board_width: i32, board_height: i32, snake: &Snake, food: (i32, i32)) -> GameState {
+        GameState {
+            board_width,
+            board_height,
+            snake: snake.snapshot(),
+            food: BlockSnapshot {
+                x: food.0,
+                y: food.1,
+            },
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("GameState should always serialize")
+    }
+}
+
+/// Picks a random empty cell on a `width` x `height` board not occupied by `snake`.
+/// Assumes at least one cell is free.
+pub fn spawn_food(width: i32, height: i32, snake: &Snake) -> (i32, i32) {
+    let occupied = snake.occupied_cells();
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..32 {
+        let x = rng.gen_range(0, width);
+        let y = rng.gen_range(0, height);
+        if !occupied.contains(&(x, y)) {
+            return (x, y);
+        }
+    }
+
+    let free_cells: Vec<(i32, i32)> = (0..width)
+        .flat_map(|x| (0..height).map(move |y| (x, y)))
+        .filter(|cell| !occupied.contains(cell))
+        .collect();
+
+    free_cells[rng.gen_range(0, free_cells.len())]
+}
+
+/// Owns a `Snake` and tracks `score`/`high_score`.
+pub struct GameSession {
+    snake: Snake,
+    score: u32,
+    high_score: u32,
+}
+
+impl GameSession {
+    pub fn new(snake: Snake) -> GameSession {
+        GameSession {
+            snake,
+            score: 0,
+            high_score: 0,
+        }
+    }
+
+    pub fn snake(&self) -> &Snake {
+        &self.snake
+    }
+
+    pub fn snake_mut(&mut self) -> &mut Snake {
+        &mut self.snake
+    }
+
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    pub fn high_score(&self) -> u32 {
+        self.high_score
+    }
+
+    pub fn eat_food(&mut self) {
+        self.snake.grow();
+        self.score += 1;
+        if self.score > self.high_score {
+            self.high_score = self.score;
+        }
+    }
+
+    /// Milliseconds between ticks; speeds up with score, floored at `FLOOR_MS`.
+    pub fn waiting_time_ms(&self) -> u32 {
+        const START_MS: u32 = 600;
+        const STEP_MS: u32 = 20;
+        const POINTS_PER_STEP: u32 = 3;
+        const FLOOR_MS: u32 = 100;
+
+        let reduction = (self.score / POINTS_PER_STEP) * STEP_MS;
+        START_MS.saturating_sub(reduction).max(FLOOR_MS)
+    }
 }
 
 #[cfg(test)]
@@ -190,4 +682,126 @@ mod tests {
         snake.restore_tail();
         assert_eq!(snake.body.len(), 4);
     }
+
+    #[test]
+    fn test_snake_autopilot_never_reverses_and_stays_in_bounds() {
+        let mut snake = Snake::new(5, 5);
+        let (board_w, board_h) = (20, 20);
+        let food = (15, 5);
+
+        for _ in 0..20 {
+            let before = snake.head_direction();
+            let dir = snake
+                .autopilot(board_w, board_h, food)
+                .expect("an open board always has a safe move");
+            assert_ne!(dir, before.opposite());
+
+            snake.move_forward(Some(dir));
+            let (x, y) = snake.head_position();
+            assert!((0..board_w).contains(&x) && (0..board_h).contains(&y));
+        }
+    }
+
+    #[test]
+    fn test_hamiltonian_cycle_is_adjacent_and_covers_largest_even_region() {
+        for &(w, h) in &[(2, 2), (4, 4), (3, 4), (6, 3), (5, 5), (3, 5)] {
+            let solver = HamiltonianSolver::new(w, h).expect("valid board should produce a cycle");
+            let n = solver.cycle_cells.len();
+            let expected = if (w * h) % 2 == 0 {
+                w * h
+            } else {
+                ((w - 1) * h).max(w * (h - 1))
+            };
+            assert_eq!(n as i32, expected);
+
+            for i in 0..n {
+                let cur = solver.cycle_cells[i];
+                let next = solver.cycle_cells[(i + 1) % n];
+                assert_eq!(
+                    (next.0 - cur.0).abs() + (next.1 - cur.1).abs(),
+                    1,
+                    "cycle step {:?} -> {:?} is not grid-adjacent for a {}x{} board",
+                    cur,
+                    next,
+                    w,
+                    h
+                );
+            }
+
+            let unique: HashSet<_> = solver.cycle_cells.iter().collect();
+            assert_eq!(unique.len(), n, "cycle must not revisit a cell");
+        }
+    }
+
+    #[test]
+    fn test_hamiltonian_solver_rejects_single_row_or_column_boards() {
+        assert!(HamiltonianSolver::new(1, 1).is_none());
+        assert!(HamiltonianSolver::new(4, 1).is_none());
+        assert!(HamiltonianSolver::new(1, 4).is_none());
+    }
+
+    #[test]
+    fn test_snake_grow_is_idempotent() {
+        let mut snake = Snake::new(2, 2);
+        snake.move_forward(Some(Direction::Right));
+        assert_eq!(snake.body.len(), 3);
+
+        snake.grow();
+        assert_eq!(snake.body.len(), 4);
+
+        // No intervening move_forward, so the stored tail is already spent.
+        snake.grow();
+        assert_eq!(snake.body.len(), 4);
+    }
+
+    #[test]
+    fn test_spawn_food_avoids_occupied_cells() {
+        let snake = Snake::new(2, 2);
+        let occupied = snake.occupied_cells();
+
+        for _ in 0..50 {
+            let food = spawn_food(6, 6, &snake);
+            assert!(!occupied.contains(&food));
+        }
+    }
+
+    #[test]
+    fn test_snake_wraps_around_board_edges() {
+        let mut snake = Snake::new(0, 0);
+        snake.set_board_mode(5, 5, BoardMode::Wrapped);
+
+        snake.move_forward(Some(Direction::Left)); // head x: 2 -> 1
+        snake.move_forward(Some(Direction::Left)); // head x: 1 -> 0
+        snake.move_forward(Some(Direction::Left)); // head x: 0 -> wraps to 4
+        assert_eq!(snake.head_position(), (4, 0));
+    }
+
+    #[test]
+    fn test_snake_snapshot_round_trip() {
+        let mut snake = Snake::new(2, 2);
+        snake.move_forward(Some(Direction::Up));
+
+        let json = serde_json::to_string(&snake.snapshot()).unwrap();
+        let snapshot: SnakeSnapshot = serde_json::from_str(&json).unwrap();
+        let restored = Snake::from_snapshot(&snapshot);
+
+        assert_eq!(restored.head_position(), snake.head_position());
+        assert_eq!(restored.head_direction(), snake.head_direction());
+        assert_eq!(restored.occupied_cells(), snake.occupied_cells());
+    }
+
+    #[test]
+    fn test_game_session_score_and_difficulty() {
+        let mut session = GameSession::new(Snake::new(2, 2));
+        assert_eq!(session.score(), 0);
+        assert_eq!(session.waiting_time_ms(), 600);
+
+        for _ in 0..9 {
+            session.eat_food();
+        }
+
+        assert_eq!(session.score(), 9);
+        assert_eq!(session.high_score(), 9);
+        assert_eq!(session.waiting_time_ms(), 540);
+    }
 }